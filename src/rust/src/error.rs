@@ -0,0 +1,70 @@
+use solana_program::{decode_error::DecodeError, program_error::ProgramError};
+use thiserror::Error;
+
+#[derive(Clone, Debug, Eq, Error, num_derive::FromPrimitive, PartialEq)]
+pub enum MintError {
+    /// New update authority account did not match the expected key
+    #[error("New update authority account did not match the expected key")]
+    UpdateAuth,
+
+    /// Source token account does not hold exactly one token
+    #[error("Source token account does not hold exactly one token")]
+    EmptyToken,
+
+    /// Source mint does not look like an NFT (decimals/supply/freeze authority)
+    #[error("Source mint does not look like an NFT")]
+    InvalidMint,
+
+    /// A derived PDA (the authority PDA or the master-edition PDA) did not match the account passed in
+    #[error("Derived PDA did not match the account passed in")]
+    AuthKeyFailure,
+
+    /// Name exceeds Metaplex's MAX_NAME_LENGTH
+    #[error("Name too long")]
+    NameTooLong,
+
+    /// Symbol exceeds Metaplex's MAX_SYMBOL_LENGTH
+    #[error("Symbol too long")]
+    SymbolTooLong,
+
+    /// Uri exceeds Metaplex's MAX_URI_LENGTH
+    #[error("Uri too long")]
+    UriTooLong,
+
+    /// seller_fee_basis_points is greater than 10000
+    #[error("Basis points cannot exceed 10000")]
+    InvalidBasisPoints,
+
+    /// Creators vector exceeds Metaplex's MAX_CREATOR_LIMIT
+    #[error("Too many creators")]
+    TooManyCreators,
+
+    /// Creator shares do not sum to 100
+    #[error("Creator shares must sum to 100")]
+    ShareTotalMustBe100,
+
+    /// token_program_account is neither the legacy spl_token program nor spl_token_2022
+    #[error("Token program must be spl_token or spl_token_2022")]
+    InvalidTokenProgram,
+
+    /// collection_mint_account did not match the collection mint carried in the rugged metadata
+    #[error("Collection mint account did not match the source NFT's collection")]
+    CollectionMintMismatch,
+
+    /// max_supply was requested for a Token-2022 mint, which the metadata program's
+    /// master-edition instruction does not support
+    #[error("Master editions are not supported for Token-2022 mints")]
+    Token2022MasterEditionUnsupported,
+}
+
+impl From<MintError> for ProgramError {
+    fn from(e: MintError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for MintError {
+    fn type_of() -> &'static str {
+        "MintError"
+    }
+}
@@ -1,15 +1,18 @@
 use {
     crate::error::MintError,
+    borsh::{BorshDeserialize, BorshSerialize},
     solana_program::{
         borsh::{try_from_slice_unchecked},
         account_info::{next_account_info, AccountInfo},
         entrypoint::ProgramResult,
+        program_option::COption,
         pubkey::Pubkey,
         program,
         program_pack::Pack,
     },
     metaplex_token_metadata::{
-        instruction::{create_metadata_accounts, update_metadata_accounts}
+        instruction::{create_master_edition, create_metadata_accounts_v2, update_metadata_accounts, verify_collection},
+        state::DataV2,
     },
     spl_token::{
         state::{
@@ -17,16 +20,64 @@ use {
             Mint
         },
     },
+    spl_token_2022::extension::StateWithExtensions,
 };
 
+/// Caller-supplied royalty and mutability terms for the clone, decoded from
+/// the instruction's `_input` bytes.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct CloneArgs {
+    pub seller_fee_basis_points: u16,
+    pub creators: Vec<(Pubkey, u8)>,
+    pub is_mutable: bool,
+    /// When set, a Master Edition is created alongside the metadata so the
+    /// clone can be printed up to this many times.
+    pub max_supply: Option<u64>,
+}
+
 const PREFIX: &str             = "amoebit_minter";
 const OUR_PUB_KEY: &str        = "VLawmZTgLAbdeqrU579ohsdey9H1h3Mi1UeUJpg2mQB";
 
+const MAX_NAME_LENGTH: usize    = 32;
+const MAX_SYMBOL_LENGTH: usize  = 10;
+const MAX_URI_LENGTH: usize     = 200;
+const MAX_CREATOR_LIMIT: usize  = 5;
+
+// ported from Metaplex's assert_data_valid so bad source metadata is rejected
+// before it ever reaches the token-metadata program
+fn assert_data_valid(data: &DataV2) -> ProgramResult {
+    if data.name.len() > MAX_NAME_LENGTH {
+        return Err(MintError::NameTooLong.into());
+    }
+    if data.symbol.len() > MAX_SYMBOL_LENGTH {
+        return Err(MintError::SymbolTooLong.into());
+    }
+    if data.uri.len() > MAX_URI_LENGTH {
+        return Err(MintError::UriTooLong.into());
+    }
+    if data.seller_fee_basis_points > 10000 {
+        return Err(MintError::InvalidBasisPoints.into());
+    }
+    if let Some(creators) = &data.creators {
+        if creators.len() > MAX_CREATOR_LIMIT {
+            return Err(MintError::TooManyCreators.into());
+        }
+        let share_total: u16 = creators.iter().map(|c| c.share as u16).sum();
+        if share_total != 100 {
+            return Err(MintError::ShareTotalMustBe100.into());
+        }
+    }
+
+    Ok(())
+}
+
 pub fn process_instruction<'a>(
     program_id: &'a Pubkey,
     accounts: &'a [AccountInfo<'a>],
     _input: &[u8],
 ) -> ProgramResult {
+    let clone_args: CloneArgs = CloneArgs::try_from_slice(_input)?;
+
     let accounts_iter           = &mut accounts.iter();
 
     let payer_account           = next_account_info(accounts_iter)?; // 0
@@ -45,15 +96,37 @@ pub fn process_instruction<'a>(
 
     if new_update_auth_account.key.to_string() != OUR_PUB_KEY { return Err(MintError::UpdateAuth.into()); }
 
-    let token_data: Account = Pack::unpack(&token_account.data.borrow())?;
-    let mint_data: Mint     = Pack::unpack(&mint_account.data.borrow())?;
+    // the clone path works against either the legacy token program or
+    // Token-2022 (which may carry extensions), so unpack via whichever of the
+    // two the caller passed as token_program_account (the owners of the token
+    // and mint accounts are not checked here)
+    let is_token_2022 = *token_program_account.key == spl_token_2022::id();
+    if !is_token_2022 && *token_program_account.key != spl_token::id() {
+        return Err(MintError::InvalidTokenProgram.into());
+    }
+
+    let (token_amount, token_mint): (u64, Pubkey) = if is_token_2022 {
+        let token = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&token_account.data.borrow())?;
+        (token.base.amount, token.base.mint)
+    } else {
+        let token: Account = Pack::unpack(&token_account.data.borrow())?;
+        (token.amount, token.mint)
+    };
+
+    let (mint_decimals, mint_supply, mint_freeze_authority): (u8, u64, COption<Pubkey>) = if is_token_2022 {
+        let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_account.data.borrow())?;
+        (mint.base.decimals, mint.base.supply, mint.base.freeze_authority)
+    } else {
+        let mint: Mint = Pack::unpack(&mint_account.data.borrow())?;
+        (mint.decimals, mint.supply, mint.freeze_authority)
+    };
 
     // Make sure client sent a proper NFT
-    if token_data.amount           != 1                 { return Err(MintError::EmptyToken.into()); }
-    if mint_data.decimals          != 0                 { return Err(MintError::InvalidMint.into()); }
-    if mint_data.supply            != 1                 { return Err(MintError::InvalidMint.into()); }
-    if !mint_data.freeze_authority.is_none()            { return Err(MintError::InvalidMint.into()); }
-    if token_data.mint             != *mint_account.key { return Err(MintError::InvalidMint.into()); }
+    if token_amount                != 1                 { return Err(MintError::EmptyToken.into()); }
+    if mint_decimals               != 0                 { return Err(MintError::InvalidMint.into()); }
+    if mint_supply                 != 1                 { return Err(MintError::InvalidMint.into()); }
+    if !mint_freeze_authority.is_none()                 { return Err(MintError::InvalidMint.into()); }
+    if token_mint                  != *mint_account.key { return Err(MintError::InvalidMint.into()); }
 
     let auth_seeds = &[
         PREFIX.as_bytes(),
@@ -76,35 +149,54 @@ pub fn process_instruction<'a>(
         return Err(MintError::AuthKeyFailure.into());
     }
 
-    let creators = vec![
+    // the PDA is always kept as a verified, zero-share creator so royalties
+    // stay enforceable no matter what split the caller asks for
+    let mut creators = vec![
         metaplex_token_metadata::state::Creator {
             address: *auth_account.key,
             verified: true,
             share: 0
         },
+    ];
+    creators.extend(clone_args.creators.iter().map(|(address, share)| {
         metaplex_token_metadata::state::Creator {
-            address: *new_update_auth_account.key,
+            address: *address,
             verified: false,
-            share: 100
-        },
-    ];
+            share: *share,
+        }
+    }));
 
     let rugged_data: metaplex_token_metadata::state::Metadata = try_from_slice_unchecked(&rugged_metadata_account.data.borrow())?;
 
-    let cmda_instruction = create_metadata_accounts(
+    // carry the rugged NFT's collection membership and Uses record into the clone
+    let data = DataV2 {
+        name: rugged_data.data.name.to_string(),
+        symbol: rugged_data.data.symbol.to_string(),
+        uri: rugged_data.data.uri.to_string(),
+        seller_fee_basis_points: clone_args.seller_fee_basis_points,
+        creators: Some(creators),
+        collection: rugged_data.collection.clone(),
+        uses: rugged_data.uses.clone(),
+    };
+
+    assert_data_valid(&data)?;
+
+    let cmda_instruction = create_metadata_accounts_v2(
         *meta_program_account.key,
         *meta_account.key,
         *mint_account.key,
         *payer_account.key,
         *payer_account.key,
         *auth_account.key,
-        rugged_data.data.name.to_string(),
-        rugged_data.data.symbol.to_string(),
-        rugged_data.data.uri.to_string(),
-        Some(creators),
-        500,
+        data.name.clone(),
+        data.symbol.clone(),
+        data.uri.clone(),
+        data.creators.clone(),
+        data.seller_fee_basis_points,
         true,
-        true
+        clone_args.is_mutable,
+        data.collection.clone(),
+        data.uses.clone(),
     );
 
     let metadata_infos = vec![
@@ -123,6 +215,46 @@ pub fn process_instruction<'a>(
         &[&authority_seeds]
     )?;
 
+    // if the source NFT belonged to a collection and our PDA is that
+    // collection's update authority, verify the clone into it so it isn't
+    // left with an unverified pointer. These accounts are only required
+    // when the source NFT actually carries a collection.
+    if let Some(collection) = data.collection.as_ref() {
+        let collection_metadata_account        = next_account_info(accounts_iter)?;
+        let collection_master_edition_account  = next_account_info(accounts_iter)?;
+        let collection_mint_account             = next_account_info(accounts_iter)?;
+
+        if *collection_mint_account.key != collection.key {
+            return Err(MintError::CollectionMintMismatch.into());
+        }
+
+        let collection_metadata: metaplex_token_metadata::state::Metadata =
+            try_from_slice_unchecked(&collection_metadata_account.data.borrow())?;
+        if collection_metadata.update_authority == auth_key {
+            program::invoke_signed(
+                &verify_collection(
+                    *meta_program_account.key,
+                    *meta_account.key,
+                    *auth_account.key,
+                    *payer_account.key,
+                    collection.key,
+                    *collection_metadata_account.key,
+                    *collection_master_edition_account.key,
+                    None,
+                ),
+                &[
+                    meta_account.clone(),
+                    auth_account.clone(),
+                    payer_account.clone(),
+                    collection_mint_account.clone(),
+                    collection_metadata_account.clone(),
+                    collection_master_edition_account.clone(),
+                ],
+                &[&authority_seeds],
+            )?;
+        }
+    }
+
     let update_infos = vec![
         meta_program_account.clone(),
         meta_account.clone(),
@@ -144,33 +276,111 @@ pub fn process_instruction<'a>(
         &[&authority_seeds],
     )?;
 
-    // disable mint
-    program::invoke(
-        &spl_token::instruction::set_authority(
-            &token_program_account.key,
-            &mint_account.key,
-            None,
-            spl_token::instruction::AuthorityType::MintTokens,
-            &payer_account.key,
-            &[&payer_account.key]
-        )?,
-        &[
-            payer_account.clone(),
-            mint_account.clone(),
-            token_program_account.clone()
-        ]
-    )?;
+    if let Some(max_supply) = clone_args.max_supply {
+        // the token-metadata program's master-edition instruction only
+        // understands the legacy spl_token program, so reject this up
+        // front instead of letting the CPI fail opaquely for Token-2022 mints
+        if is_token_2022 {
+            return Err(MintError::Token2022MasterEditionUnsupported.into());
+        }
+
+        // the caller asked for a printable clone: create a Master Edition
+        // while we still hold mint authority so it can be printed up to
+        // max_supply times. This transfers mint authority to the edition PDA
+        // (our freeze-authority invariant above guarantees there is no
+        // freeze authority to transfer alongside it), which already locks
+        // further minting, so there's no separate disable-mint step here.
+        let edition_account = next_account_info(accounts_iter)?;
+
+        let edition_seeds = &[
+            "metadata".as_bytes(),
+            meta_program_account.key.as_ref(),
+            mint_account.key.as_ref(),
+            "edition".as_bytes(),
+        ];
+        let (edition_key, _) = Pubkey::find_program_address(edition_seeds, meta_program_account.key);
+        if edition_key != *edition_account.key {
+            return Err(MintError::AuthKeyFailure.into());
+        }
+
+        program::invoke_signed(
+            &create_master_edition(
+                *meta_program_account.key,
+                *edition_account.key,
+                *mint_account.key,
+                *auth_account.key,
+                *payer_account.key,
+                *meta_account.key,
+                *payer_account.key,
+                Some(max_supply),
+            ),
+            &[
+                edition_account.clone(),
+                mint_account.clone(),
+                auth_account.clone(),
+                payer_account.clone(),
+                meta_account.clone(),
+                meta_program_account.clone(),
+                token_program_account.clone(),
+                sys_account.clone(),
+                rent_account.clone(),
+            ],
+            &[&authority_seeds],
+        )?;
+    } else {
+        // no printable edition requested: this is a one-off clone, so
+        // revoke the mint authority the way the program always has
+        let disable_mint_ix = if is_token_2022 {
+            spl_token_2022::instruction::set_authority(
+                &token_program_account.key,
+                &mint_account.key,
+                None,
+                spl_token_2022::instruction::AuthorityType::MintTokens,
+                &payer_account.key,
+                &[&payer_account.key]
+            )?
+        } else {
+            spl_token::instruction::set_authority(
+                &token_program_account.key,
+                &mint_account.key,
+                None,
+                spl_token::instruction::AuthorityType::MintTokens,
+                &payer_account.key,
+                &[&payer_account.key]
+            )?
+        };
+        program::invoke(
+            &disable_mint_ix,
+            &[
+                payer_account.clone(),
+                mint_account.clone(),
+                token_program_account.clone()
+            ]
+        )?;
+    }
 
     // burn in hell
-    program::invoke(
-        &spl_token::instruction::burn(
+    let burn_rugged_ix = if is_token_2022 {
+        spl_token_2022::instruction::burn(
             &token_program_account.key,
             &rugged_token_account.key,
             &rugged_account.key,
             &payer_account.key,
             &[&payer_account.key],
             1
-        )?,
+        )?
+    } else {
+        spl_token::instruction::burn(
+            &token_program_account.key,
+            &rugged_token_account.key,
+            &rugged_account.key,
+            &payer_account.key,
+            &[&payer_account.key],
+            1
+        )?
+    };
+    program::invoke(
+        &burn_rugged_ix,
         &[
             token_program_account.clone(),
             rugged_token_account.clone(),